@@ -1,7 +1,8 @@
-use std::{cmp, fmt, ptr};
+use std::{cmp, fmt, mem, ptr};
 use std::os::raw::c_int;
 use std::os::unix::io::RawFd;
 use std::collections::HashMap;
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
 use std::time::Duration;
 
@@ -19,22 +20,73 @@ use sys::unix::io::set_cloexec;
 /// operation will return with an error. This matches windows behavior.
 static NEXT_ID: AtomicUsize = ATOMIC_USIZE_INIT;
 
+/// Reserved `ident` used for the internal `EVFILT_USER` wakeup. It is chosen
+/// far outside the range of any real file descriptor so it cannot collide with
+/// a registered source.
+const AWAKEN_IDENT: libc::uintptr_t = ::std::usize::MAX as libc::uintptr_t;
+
 macro_rules! kevent {
     ($id: expr, $filter: expr, $flags: expr, $data: expr) => {
+        kevent!($id, $filter, $flags, 0, 0, $data)
+    };
+    ($id: expr, $filter: expr, $flags: expr, $fflags: expr, $data: expr, $udata: expr) => {
         libc::kevent {
             ident: $id as ::libc::uintptr_t,
             filter: $filter,
             flags: $flags,
-            fflags: 0,
-            data: 0,
-            udata: $data as *mut _,
+            fflags: $fflags,
+            data: $data,
+            udata: $udata as *mut _,
         }
     }
 }
 
+/// The set of process lifecycle events to watch for with `register_process`.
+/// Each variant maps directly onto an `EVFILT_PROC` note.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ProcessInterest(u32);
+
+impl ProcessInterest {
+    /// The process exited (`NOTE_EXIT`).
+    pub fn exit() -> ProcessInterest {
+        ProcessInterest(libc::NOTE_EXIT)
+    }
+
+    /// The process called `fork` (`NOTE_FORK`).
+    pub fn fork() -> ProcessInterest {
+        ProcessInterest(libc::NOTE_FORK)
+    }
+
+    /// The process called `exec` (`NOTE_EXEC`).
+    pub fn exec() -> ProcessInterest {
+        ProcessInterest(libc::NOTE_EXEC)
+    }
+
+    /// Whether `self` includes every note in `other`.
+    pub fn contains(&self, other: ProcessInterest) -> bool {
+        (self.0 & other.0) == other.0
+    }
+}
+
+impl ::std::ops::BitOr for ProcessInterest {
+    type Output = ProcessInterest;
+
+    fn bitor(self, rhs: ProcessInterest) -> ProcessInterest {
+        ProcessInterest(self.0 | rhs.0)
+    }
+}
+
 pub struct Selector {
     id: usize,
     kq: RawFd,
+    // Whether this selector was able to register an `EVFILT_USER` wakeup. When
+    // `false` (older NetBSD/OpenBSD that lack the filter) callers must fall
+    // back to the external pipe-based awakener `Token` passed to `select`.
+    use_evfilt_user: bool,
+    // For each signal registered with `register_signal`, whether it was already
+    // blocked before we blocked it. `deregister_signal` uses this to restore
+    // the caller's prior disposition instead of forcing `SIG_UNBLOCK`.
+    signals: Mutex<HashMap<c_int, bool>>,
 }
 
 impl Selector {
@@ -44,12 +96,60 @@ impl Selector {
         let kq = unsafe { try!(cvt(libc::kqueue())) };
         drop(set_cloexec(kq));
 
+        // Try to install the `EVFILT_USER` wakeup up front. On platforms that
+        // lack the filter the registration fails with `EINVAL` and we keep the
+        // pipe-based awakener as a fallback.
+        let use_evfilt_user = register_wakeup(kq);
+
         Ok(Selector {
             id: id,
             kq: kq,
+            use_evfilt_user: use_evfilt_user,
+            signals: Mutex::new(HashMap::new()),
         })
     }
 
+    /// Returns `true` when the internal `EVFILT_USER` wakeup is available. The
+    /// awakener layer queries this once at construction: when it is `true` it
+    /// skips allocating the self-pipe (saving the two fds) and wakes the
+    /// selector through `wakeup`; when it is `false` it falls back to the pipe
+    /// awakener `Token` passed to `select`.
+    pub fn has_user_wakeup(&self) -> bool {
+        self.use_evfilt_user
+    }
+
+    /// Unblock a `select` call currently blocked on this selector by triggering
+    /// the internal `EVFILT_USER` event. This is the wakeup path used whenever
+    /// `has_user_wakeup` is `true`; when the filter is unavailable it is a
+    /// no-op and the awakener layer writes to the pipe awakener instead.
+    #[cfg(not(target_os = "openbsd"))]
+    pub fn wakeup(&self) -> io::Result<()> {
+        if !self.use_evfilt_user {
+            return Ok(());
+        }
+
+        unsafe {
+            let mut changes = [kevent!(AWAKEN_IDENT, libc::EVFILT_USER, libc::EV_RECEIPT,
+                                       libc::NOTE_TRIGGER, 0, ptr::null_mut())];
+            try!(cvt(libc::kevent(self.kq, changes.as_ptr(), changes.len() as c_int,
+                                           changes.as_mut_ptr(), changes.len() as c_int,
+                                           ptr::null())));
+            if changes[0].data != 0 {
+                return Err(::std::io::Error::from_raw_os_error(changes[0].data as i32));
+            }
+        }
+        Ok(())
+    }
+
+    // OpenBSD's `libc` does not define `EVFILT_USER`/`NOTE_TRIGGER`, so the
+    // filter cannot even be named there. `use_evfilt_user` is always `false` on
+    // those targets (see `register_wakeup`), so this path is never taken; it is
+    // kept only so the crate compiles.
+    #[cfg(target_os = "openbsd")]
+    pub fn wakeup(&self) -> io::Result<()> {
+        Ok(())
+    }
+
     pub fn id(&self) -> usize {
         self.id
     }
@@ -72,8 +172,192 @@ impl Selector {
                                             evts.sys_events.0.capacity() as c_int,
                                             timeout)));
             evts.sys_events.0.set_len(cnt as usize);
-            Ok(evts.coalesce(awakener))
+            Ok(evts.coalesce(awakener, self.use_evfilt_user))
+        }
+    }
+
+    /// Register a kqueue timer for `token` that fires after `delay`. When
+    /// `periodic` is `true` the timer rearms automatically; otherwise it fires
+    /// a single time (`EV_ONESHOT`).
+    ///
+    /// A `delay` of zero would fire immediately and repeatedly, so it is
+    /// clamped to the smallest representable interval.
+    pub fn register_timer(&self, token: Token, delay: Duration, periodic: bool) -> io::Result<()> {
+        let (data, fflags) = timer_data(delay);
+        let flags = libc::EV_ADD | libc::EV_RECEIPT |
+                    if periodic { 0 } else { libc::EV_ONESHOT };
+
+        unsafe {
+            let mut changes = [kevent!(usize::from(token), libc::EVFILT_TIMER, flags,
+                                       fflags, data, usize::from(token))];
+            try!(cvt(libc::kevent(self.kq, changes.as_ptr(), changes.len() as c_int,
+                                           changes.as_mut_ptr(), changes.len() as c_int,
+                                           ptr::null())));
+            if changes[0].data != 0 {
+                return Err(::std::io::Error::from_raw_os_error(changes[0].data as i32));
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove a timer previously registered with `register_timer`. A timer that
+    /// has already fired as a one-shot is gone, so `ENOENT` is ignored just as
+    /// it is for `deregister`.
+    pub fn deregister_timer(&self, token: Token) -> io::Result<()> {
+        unsafe {
+            let mut changes = [kevent!(usize::from(token), libc::EVFILT_TIMER,
+                                       libc::EV_DELETE | libc::EV_RECEIPT, ptr::null_mut())];
+            try!(cvt(libc::kevent(self.kq, changes.as_ptr(), changes.len() as c_int,
+                                           changes.as_mut_ptr(), changes.len() as c_int,
+                                           ptr::null())));
+            if changes[0].data != 0 && changes[0].data as i32 != libc::ENOENT {
+                return Err(::std::io::Error::from_raw_os_error(changes[0].data as i32));
+            }
+        }
+        Ok(())
+    }
+
+    /// Watch the child process `pid` for the lifecycle events in `events`
+    /// (`NOTE_EXIT`, `NOTE_FORK`, `NOTE_EXEC`). This lets async runtimes reap
+    /// children without installing a `SIGCHLD` handler.
+    pub fn register_process(&self, token: Token, pid: libc::pid_t, events: ProcessInterest) -> io::Result<()> {
+        let fflags = process_fflags(events.0);
+        unsafe {
+            let mut changes = [kevent!(pid, libc::EVFILT_PROC,
+                                       libc::EV_ADD | libc::EV_CLEAR | libc::EV_RECEIPT,
+                                       fflags, 0, usize::from(token))];
+            try!(cvt(libc::kevent(self.kq, changes.as_ptr(), changes.len() as c_int,
+                                           changes.as_mut_ptr(), changes.len() as c_int,
+                                           ptr::null())));
+            if changes[0].data != 0 {
+                return Err(::std::io::Error::from_raw_os_error(changes[0].data as i32));
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop watching `pid`. A `NOTE_EXIT` auto-clears the registration once the
+    /// process is gone, so `ESRCH`/`ENOENT` are ignored the same way
+    /// `deregister` ignores `ENOENT`.
+    pub fn deregister_process(&self, pid: libc::pid_t) -> io::Result<()> {
+        unsafe {
+            let mut changes = [kevent!(pid, libc::EVFILT_PROC,
+                                       libc::EV_DELETE | libc::EV_RECEIPT, ptr::null_mut())];
+            try!(cvt(libc::kevent(self.kq, changes.as_ptr(), changes.len() as c_int,
+                                           changes.as_mut_ptr(), changes.len() as c_int,
+                                           ptr::null())));
+            let err = changes[0].data as i32;
+            if changes[0].data != 0 && err != libc::ENOENT && err != libc::ESRCH {
+                return Err(::std::io::Error::from_raw_os_error(err));
+            }
+        }
+        Ok(())
+    }
+
+    /// Deliver `signum` as a kqueue event for `token`. `EVFILT_SIGNAL` only
+    /// observes signals whose default disposition is not taken, so the signal
+    /// is blocked (via `pthread_sigmask`) before the filter is registered; its
+    /// prior disposition is saved and restored by `deregister_signal`.
+    ///
+    /// Note: `pthread_sigmask` adjusts the mask of the *calling* thread only.
+    /// In a multi-threaded process the signal can still be delivered to another
+    /// thread whose mask leaves it unblocked and run that thread's default
+    /// disposition, never reaching the kqueue. For reliable delivery the signal
+    /// must be blocked on every thread — typically by calling this before any
+    /// other threads are spawned, so they inherit the blocked mask.
+    pub fn register_signal(&self, token: Token, signum: c_int) -> io::Result<()> {
+        // Hold the lock across the whole check-block-register-record sequence so
+        // that two concurrent registrations of the same `signum` cannot both
+        // observe `first == true`, read our own block as the caller's prior
+        // disposition, and clobber the genuine saved value.
+        let mut signals = self.signals.lock().unwrap();
+        unsafe {
+            // A signal is blocked (and its prior disposition saved) only on the
+            // first registration. Re-registering the same `signum` must not
+            // re-observe our own block as the caller's, nor overwrite the saved
+            // disposition, or `deregister_signal` would never restore it.
+            let first = !signals.contains_key(&signum);
+
+            // Block the signal so it is queued rather than run through its
+            // default disposition, saving the previous mask so we can tell
+            // whether the caller had already blocked it. `pthread_sigmask`
+            // returns the error number directly rather than through `errno`.
+            let mut set: libc::sigset_t = mem::zeroed();
+            libc::sigemptyset(&mut set);
+            libc::sigaddset(&mut set, signum);
+            let mut was_blocked = true;
+            if first {
+                let mut oldset: libc::sigset_t = mem::zeroed();
+                libc::sigemptyset(&mut oldset);
+                let r = libc::pthread_sigmask(libc::SIG_BLOCK, &set, &mut oldset);
+                if r != 0 {
+                    return Err(::std::io::Error::from_raw_os_error(r));
+                }
+                was_blocked = libc::sigismember(&oldset, signum) == 1;
+            }
+
+            let mut changes = [kevent!(signum, libc::EVFILT_SIGNAL,
+                                       libc::EV_ADD | libc::EV_CLEAR | libc::EV_RECEIPT,
+                                       0, 0, usize::from(token))];
+            let res = cvt(libc::kevent(self.kq, changes.as_ptr(), changes.len() as c_int,
+                                               changes.as_mut_ptr(), changes.len() as c_int,
+                                               ptr::null()))
+                .and_then(|_| {
+                    if changes[0].data != 0 {
+                        Err(::std::io::Error::from_raw_os_error(changes[0].data as i32))
+                    } else {
+                        Ok(())
+                    }
+                });
+
+            // If registration failed, undo the mask change so a failed call
+            // does not silently leave the caller's signal mask mutated. Only a
+            // first registration that actually blocked the signal needs undoing.
+            if res.is_err() {
+                if first && !was_blocked {
+                    libc::pthread_sigmask(libc::SIG_UNBLOCK, &set, ptr::null_mut());
+                }
+                return res;
+            }
+
+            // Record the caller's prior disposition on first registration only;
+            // a re-registration must not clobber the saved value.
+            if first {
+                signals.insert(signum, was_blocked);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop delivering `signum` as an event and restore the disposition it had
+    /// before `register_signal` blocked it. `ENOENT` is ignored just as it is
+    /// for `deregister`.
+    pub fn deregister_signal(&self, signum: c_int) -> io::Result<()> {
+        unsafe {
+            let mut changes = [kevent!(signum, libc::EVFILT_SIGNAL,
+                                       libc::EV_DELETE | libc::EV_RECEIPT, ptr::null_mut())];
+            try!(cvt(libc::kevent(self.kq, changes.as_ptr(), changes.len() as c_int,
+                                           changes.as_mut_ptr(), changes.len() as c_int,
+                                           ptr::null())));
+            if changes[0].data != 0 && changes[0].data as i32 != libc::ENOENT {
+                return Err(::std::io::Error::from_raw_os_error(changes[0].data as i32));
+            }
+
+            // Only unblock the signal if it was not already blocked when we
+            // registered it; otherwise the application blocked it on purpose
+            // and we must leave it alone.
+            let was_blocked = self.signals.lock().unwrap().remove(&signum);
+            if let Some(false) = was_blocked {
+                let mut set: libc::sigset_t = mem::zeroed();
+                libc::sigemptyset(&mut set);
+                libc::sigaddset(&mut set, signum);
+                let r = libc::pthread_sigmask(libc::SIG_UNBLOCK, &set, ptr::null_mut());
+                if r != 0 {
+                    return Err(::std::io::Error::from_raw_os_error(r));
+                }
+            }
         }
+        Ok(())
     }
 
     pub fn register(&self, fd: RawFd, token: Token, interests: Ready, opts: PollOpt) -> io::Result<()> {
@@ -163,6 +447,71 @@ impl Selector {
     }
 }
 
+/// Attempt to register the reserved `EVFILT_USER` wakeup on `kq`. Returns
+/// `true` on success and `false` when the platform rejects the filter with
+/// `EINVAL`, signalling that the pipe fallback must be used.
+#[cfg(not(target_os = "openbsd"))]
+fn register_wakeup(kq: RawFd) -> bool {
+    unsafe {
+        let mut changes = [kevent!(AWAKEN_IDENT, libc::EVFILT_USER,
+                                   libc::EV_ADD | libc::EV_CLEAR | libc::EV_RECEIPT, ptr::null_mut())];
+        let r = libc::kevent(kq, changes.as_ptr(), changes.len() as c_int,
+                                 changes.as_mut_ptr(), changes.len() as c_int, ptr::null());
+        if r < 0 {
+            return false;
+        }
+        // `EV_RECEIPT` always reports the per-change result in `data`; a zero
+        // means the filter was installed.
+        changes[0].data == 0
+    }
+}
+
+// OpenBSD ships a `libc` without `EVFILT_USER`, so the filter cannot be
+// referenced at compile time and the runtime `EINVAL` probe above is unusable.
+// Hard-code the pipe fallback there; NetBSD goes through the runtime probe.
+#[cfg(target_os = "openbsd")]
+fn register_wakeup(_kq: RawFd) -> bool {
+    false
+}
+
+/// Convert a timer `delay` into the `(data, fflags)` pair used by
+/// `EVFILT_TIMER`. macOS understands nanosecond resolution via `NOTE_NSECONDS`;
+/// older BSDs lack the unit fflags and interpret `data` as milliseconds.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn timer_data(delay: Duration) -> (libc::intptr_t, u32) {
+    let nanos = delay.as_secs()
+        .saturating_mul(1_000_000_000)
+        .saturating_add(delay.subsec_nanos() as u64);
+    (cmp::max(nanos, 1) as libc::intptr_t, libc::NOTE_NSECONDS)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+fn timer_data(delay: Duration) -> (libc::intptr_t, u32) {
+    let millis = delay.as_secs()
+        .saturating_mul(1_000)
+        .saturating_add((delay.subsec_nanos() / 1_000_000) as u64);
+    (cmp::max(millis, 1) as libc::intptr_t, 0)
+}
+
+/// Augment the requested `EVFILT_PROC` notes per platform. On Darwin a bare
+/// `NOTE_EXIT` yields only the low byte of the status, so we also ask for
+/// `NOTE_EXITSTATUS` to get the full `wait(2)` status word in `data`. Other
+/// BSDs have no such note and deliver a bare exit code, so the notes are passed
+/// through unchanged.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn process_fflags(notes: u32) -> u32 {
+    if notes & libc::NOTE_EXIT != 0 {
+        notes | libc::NOTE_EXITSTATUS
+    } else {
+        notes
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+fn process_fflags(notes: u32) -> u32 {
+    notes
+}
+
 impl fmt::Debug for Selector {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("Selector")
@@ -184,6 +533,70 @@ pub struct Events {
     sys_events: KeventList,
     events: Vec<Event>,
     event_map: HashMap<Token, usize>,
+    // Filter-specific data that accompanies each coalesced event but does not
+    // fit in the portable `Ready` set. Indexed like `events` for the events
+    // built by `coalesce`; events added with `push_event` have no entry here.
+    event_data: Vec<EventData>,
+}
+
+/// Extra data attached to an `Event` for filters whose result does not fit in
+/// `Ready`. Fetch it with `Events::data` using the same index passed to
+/// `Events::get`.
+#[derive(Clone, Copy, Debug)]
+pub struct EventData {
+    timer_count: i64,
+    process_fflags: u32,
+    process_data: i64,
+    signal_count: i64,
+}
+
+impl EventData {
+    fn new() -> EventData {
+        EventData {
+            timer_count: 0,
+            process_fflags: 0,
+            process_data: 0,
+            signal_count: 0,
+        }
+    }
+
+    /// For an `EVFILT_TIMER` event, the number of times the timer elapsed since
+    /// the last `select`. Zero for events from other filters.
+    pub fn timer_count(&self) -> i64 {
+        self.timer_count
+    }
+
+    /// For an `EVFILT_PROC` event, whether it includes the lifecycle notes in
+    /// `interest` (`NOTE_EXIT`, `NOTE_FORK`, `NOTE_EXEC`).
+    pub fn process_contains(&self, interest: ProcessInterest) -> bool {
+        (self.process_fflags & interest.0) == interest.0
+    }
+
+    /// For an `EVFILT_PROC` event carrying `NOTE_EXIT`, the value the kernel
+    /// placed in the event's `data`. `None` if the process did not exit.
+    ///
+    /// The meaning of the value is platform-specific:
+    ///
+    /// * On macOS/iOS `register_process` additionally requests `NOTE_EXITSTATUS`,
+    ///   so `data` is the raw `wait(2)` status word — *not* a decoded exit code.
+    ///   Decode it with `libc::WEXITSTATUS` / `libc::WIFSIGNALED` /
+    ///   `libc::WTERMSIG` just as you would a `waitpid` result.
+    /// * On the other BSDs there is no `NOTE_EXITSTATUS`; the kernel reports a
+    ///   bare exit code, which is returned as-is.
+    pub fn exit_status(&self) -> Option<i32> {
+        if self.process_fflags & libc::NOTE_EXIT != 0 {
+            Some(self.process_data as i32)
+        } else {
+            None
+        }
+    }
+
+    /// For an `EVFILT_SIGNAL` event, the coalesced number of times the signal
+    /// was delivered since the last `select`. Zero for events from other
+    /// filters.
+    pub fn signal_count(&self) -> i64 {
+        self.signal_count
+    }
 }
 
 struct KeventList(Vec<libc::kevent>);
@@ -196,7 +609,8 @@ impl Events {
         Events {
             sys_events: KeventList(Vec::with_capacity(cap)),
             events: Vec::with_capacity(cap),
-            event_map: HashMap::with_capacity(cap)
+            event_map: HashMap::with_capacity(cap),
+            event_data: Vec::with_capacity(cap),
         }
     }
 
@@ -219,16 +633,37 @@ impl Events {
         self.events.get(idx).map(|e| *e)
     }
 
-    fn coalesce(&mut self, awakener: Token) -> bool {
+    /// Filter-specific data for the event at `idx` (see `EventData`).
+    pub fn data(&self, idx: usize) -> Option<EventData> {
+        self.event_data.get(idx).map(|d| *d)
+    }
+
+    fn coalesce(&mut self, awakener: Token, use_user_wakeup: bool) -> bool {
         let mut ret = false;
         self.events.clear();
         self.event_map.clear();
+        self.event_data.clear();
 
         for e in self.sys_events.0.iter() {
             let token = Token(e.udata as usize);
             let len = self.events.len();
 
-            if token == awakener {
+            // Exactly one wakeup path is active per selector. When the
+            // `EVFILT_USER` filter is available it is the sole wakeup and the
+            // awakener `Token` is never registered, so the token comparison is
+            // dropped entirely; otherwise (OpenBSD/old NetBSD) the pipe
+            // awakener `Token` is the wakeup instead.
+            if use_user_wakeup {
+                #[cfg(not(target_os = "openbsd"))]
+                {
+                    if e.filter == libc::EVFILT_USER {
+                        // The internal `EVFILT_USER` wakeup fired; it carries no
+                        // token, so swallow it as a wakeup.
+                        ret = true;
+                        continue;
+                    }
+                }
+            } else if token == awakener {
                 // TODO: Should this return an error if event is an error. It
                 // is not critical as spurious wakeups are permitted.
                 ret = true;
@@ -241,7 +676,7 @@ impl Events {
             if idx == len {
                 // New entry, insert the default
                 self.events.push(Event::new(Ready::empty(), token));
-
+                self.event_data.push(EventData::new());
             }
 
             if e.flags & libc::EV_ERROR != 0 {
@@ -252,6 +687,29 @@ impl Events {
                 event::kind_mut(&mut self.events[idx]).insert(Ready::readable());
             } else if e.filter == libc::EVFILT_WRITE {
                 event::kind_mut(&mut self.events[idx]).insert(Ready::writable());
+            } else if e.filter == libc::EVFILT_TIMER {
+                // A timer elapsed; surface it as readable for the registered
+                // token and record how many times it fired since the last
+                // `select` (accumulating across coalesced events) so periodic
+                // callers can catch up on missed ticks.
+                event::kind_mut(&mut self.events[idx]).insert(Ready::readable());
+                self.event_data[idx].timer_count += e.data as i64;
+            } else if e.filter == libc::EVFILT_PROC {
+                // A watched process reported a lifecycle event. Surface it as
+                // readable and record which notes fired (`e.fflags`) plus, for
+                // `NOTE_EXIT`, the exit value in `e.data` so callers can reap
+                // the child without a `SIGCHLD` handler. The value is stored
+                // undecoded; `EventData::exit_status` documents what it means
+                // on each platform.
+                event::kind_mut(&mut self.events[idx]).insert(Ready::readable());
+                self.event_data[idx].process_fflags |= e.fflags;
+                self.event_data[idx].process_data = e.data as i64;
+            } else if e.filter == libc::EVFILT_SIGNAL {
+                // A registered signal was delivered; surface it as readable and
+                // record the coalesced count of deliveries since the last
+                // `select` so callers can detect folded signals.
+                event::kind_mut(&mut self.events[idx]).insert(Ready::readable());
+                self.event_data[idx].signal_count += e.data as i64;
             }
 
             if e.flags & libc::EV_EOF != 0 {
@@ -299,3 +757,121 @@ fn does_not_register_rw() {
     evtloop.register(&kqf, Token(1234), Ready::readable(),
                      PollOpt::edge() | PollOpt::oneshot()).unwrap();
 }
+
+// These exercise the kqueue-only filters added for wakeup/timer/signal support.
+// They are gated to targets where `EVFILT_USER`/`EVFILT_TIMER`/`EVFILT_SIGNAL`
+// are available and verifiable under CI (macOS/iOS).
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+mod kqueue_filter_tests {
+    use std::time::Duration;
+
+    use libc;
+    use super::{Selector, Events};
+    use Token;
+
+    // An unused awakener token; these tests never register the self-pipe.
+    const NO_AWAKENER: Token = Token(::std::usize::MAX - 1);
+
+    #[test]
+    fn evfilt_user_wakeup_round_trip() {
+        let selector = Selector::new().unwrap();
+        assert!(selector.has_user_wakeup(), "macOS provides EVFILT_USER");
+
+        // Trigger the internal wakeup, then a blocking `select` must return
+        // immediately reporting the wakeup and surface no user-visible events.
+        selector.wakeup().unwrap();
+
+        let mut events = Events::with_capacity(16);
+        let woken = selector.select(&mut events, NO_AWAKENER,
+                                    Some(Duration::from_secs(1))).unwrap();
+        assert!(woken, "wakeup() should unblock select and report a wakeup");
+        assert_eq!(events.len(), 0, "wakeup carries no user event");
+    }
+
+    #[test]
+    fn evfilt_timer_oneshot_fires_once() {
+        let selector = Selector::new().unwrap();
+        let token = Token(7);
+        selector.register_timer(token, Duration::from_millis(10), false).unwrap();
+
+        let mut events = Events::with_capacity(16);
+        let woken = selector.select(&mut events, NO_AWAKENER,
+                                    Some(Duration::from_secs(1))).unwrap();
+        assert!(!woken);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events.get(0).unwrap().token(), token);
+        assert!(events.get(0).unwrap().readiness().is_readable());
+        assert!(events.data(0).unwrap().timer_count() >= 1);
+
+        // A one-shot timer does not rearm, so the next wait times out empty.
+        let woken = selector.select(&mut events, NO_AWAKENER,
+                                    Some(Duration::from_millis(50))).unwrap();
+        assert!(!woken);
+        assert_eq!(events.len(), 0);
+    }
+
+    #[test]
+    fn evfilt_timer_periodic_rearms() {
+        let selector = Selector::new().unwrap();
+        let token = Token(8);
+        selector.register_timer(token, Duration::from_millis(10), true).unwrap();
+
+        let mut events = Events::with_capacity(16);
+        for _ in 0..2 {
+            selector.select(&mut events, NO_AWAKENER,
+                            Some(Duration::from_secs(1))).unwrap();
+            assert_eq!(events.get(0).unwrap().token(), token);
+            assert!(events.data(0).unwrap().timer_count() >= 1);
+        }
+
+        selector.deregister_timer(token).unwrap();
+    }
+
+    #[test]
+    fn evfilt_signal_delivery_and_mask_restore() {
+        let selector = Selector::new().unwrap();
+        let token = Token(9);
+        let signum = libc::SIGUSR1;
+
+        // The signal must be unblocked to start, so we can observe it being
+        // restored to that state after deregistration.
+        assert!(!signal_blocked(signum));
+
+        selector.register_signal(token, signum).unwrap();
+        assert!(signal_blocked(signum), "register_signal blocks the signal");
+
+        unsafe { libc::raise(signum); }
+
+        let mut events = Events::with_capacity(16);
+        let woken = selector.select(&mut events, NO_AWAKENER,
+                                    Some(Duration::from_secs(1))).unwrap();
+        assert!(!woken);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events.get(0).unwrap().token(), token);
+        assert!(events.data(0).unwrap().signal_count() >= 1);
+
+        // kqueue only records the signal; it is still pending on this thread.
+        // Consume it so that unblocking it below does not run its default
+        // action and terminate the test process.
+        unsafe {
+            let mut set: libc::sigset_t = ::std::mem::zeroed();
+            libc::sigemptyset(&mut set);
+            libc::sigaddset(&mut set, signum);
+            let mut sig = 0;
+            libc::sigwait(&set, &mut sig);
+        }
+
+        selector.deregister_signal(signum).unwrap();
+        assert!(!signal_blocked(signum), "deregister_signal restores the mask");
+    }
+
+    // Whether `signum` is currently blocked on the calling thread.
+    fn signal_blocked(signum: ::std::os::raw::c_int) -> bool {
+        unsafe {
+            let mut set: libc::sigset_t = ::std::mem::zeroed();
+            libc::sigemptyset(&mut set);
+            libc::pthread_sigmask(libc::SIG_BLOCK, ::std::ptr::null(), &mut set);
+            libc::sigismember(&set, signum) == 1
+        }
+    }
+}